@@ -0,0 +1,229 @@
+/// Pluggable Sudoku constraints.
+///
+/// The generator's fast bitmask paths hardcode row/column/box rules. This module
+/// lifts each rule behind a [`Constraint`] trait so variants can be composed: a
+/// constraint set always includes [`BaseSudoku`] and may add diagonal
+/// (X-Sudoku), windoku, anti-knight or killer-cage rules on top.
+
+/// A single placement rule over the grid.
+pub trait Constraint: Send + Sync {
+    /// Whether placing `value` at `pos` is allowed given the current `grid`.
+    ///
+    /// The default checks that no other cell sharing this constraint already
+    /// holds `value`; constraints that are not pure "all-different" rules (e.g.
+    /// killer cages) override it.
+    fn allows(&self, grid: &[i32], size: usize, pos: usize, value: i32) -> bool {
+        for cell in self.affected_cells(size, pos) {
+            if cell != pos && grid[cell] == value {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Cells that share this constraint with `pos` (may include `pos`).
+    fn affected_cells(&self, size: usize, pos: usize) -> Vec<usize>;
+}
+
+/// Built-in selectable variants exposed through the generate NIF.
+///
+/// Killer cages are not selectable here because they require per-puzzle cage
+/// data; they are available directly through the [`Constraint`] API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Classic,
+    XSudoku,
+    Windoku,
+    AntiKnight,
+}
+
+impl Variant {
+    /// Maps the integer selector passed from Elixir to a variant.
+    pub fn from_code(code: i32) -> Option<Variant> {
+        match code {
+            0 => Some(Variant::Classic),
+            1 => Some(Variant::XSudoku),
+            2 => Some(Variant::Windoku),
+            3 => Some(Variant::AntiKnight),
+            _ => None,
+        }
+    }
+}
+
+/// Builds the active constraint set for a variant; always includes the base.
+pub fn constraints_for(variant: Variant, size: usize) -> Vec<Box<dyn Constraint>> {
+    let mut set: Vec<Box<dyn Constraint>> = vec![Box::new(BaseSudoku)];
+    match variant {
+        Variant::Classic => {}
+        Variant::XSudoku => set.push(Box::new(Diagonal)),
+        Variant::Windoku => set.push(Box::new(Windoku)),
+        Variant::AntiKnight => set.push(Box::new(AntiKnight)),
+    }
+    let _ = size; // size-specific sets may be added per variant in future.
+    set
+}
+
+/// Checks a placement against every constraint in the set.
+pub fn placement_allowed(
+    constraints: &[Box<dyn Constraint>],
+    grid: &[i32],
+    size: usize,
+    pos: usize,
+    value: i32,
+) -> bool {
+    constraints.iter().all(|c| c.allows(grid, size, pos, value))
+}
+
+/// The base row/column/box uniqueness rule present in every variant.
+pub struct BaseSudoku;
+
+impl Constraint for BaseSudoku {
+    fn affected_cells(&self, size: usize, pos: usize) -> Vec<usize> {
+        let sub = (size as f64).sqrt() as usize;
+        let row = pos / size;
+        let col = pos % size;
+        let mut cells = Vec::new();
+
+        for c in 0..size {
+            cells.push(row * size + c);
+        }
+        for r in 0..size {
+            push_unique(&mut cells, r * size + col);
+        }
+        let box_row = (row / sub) * sub;
+        let box_col = (col / sub) * sub;
+        for r in box_row..box_row + sub {
+            for c in box_col..box_col + sub {
+                push_unique(&mut cells, r * size + c);
+            }
+        }
+        cells
+    }
+}
+
+/// Main-diagonal uniqueness (X-Sudoku): both long diagonals are all-different.
+pub struct Diagonal;
+
+impl Constraint for Diagonal {
+    fn affected_cells(&self, size: usize, pos: usize) -> Vec<usize> {
+        let row = pos / size;
+        let col = pos % size;
+        let mut cells = Vec::new();
+        if row == col {
+            for i in 0..size {
+                cells.push(i * size + i);
+            }
+        }
+        if row + col == size - 1 {
+            for i in 0..size {
+                push_unique(&mut cells, i * size + (size - 1 - i));
+            }
+        }
+        cells
+    }
+}
+
+/// The four "windoku" 3x3 windows inside a 9x9 grid (rows/cols 1-3 and 5-7).
+pub struct Windoku;
+
+impl Windoku {
+    const WINDOWS: [(usize, usize); 4] = [(1, 1), (1, 5), (5, 1), (5, 5)];
+}
+
+impl Constraint for Windoku {
+    fn affected_cells(&self, size: usize, pos: usize) -> Vec<usize> {
+        let mut cells = Vec::new();
+        if size != 9 {
+            return cells; // Windoku is only defined on the classic 9x9 board.
+        }
+        let row = pos / size;
+        let col = pos % size;
+        for &(wr, wc) in Windoku::WINDOWS.iter() {
+            if row >= wr && row < wr + 3 && col >= wc && col < wc + 3 {
+                for r in wr..wr + 3 {
+                    for c in wc..wc + 3 {
+                        push_unique(&mut cells, r * size + c);
+                    }
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// Anti-knight: equal digits may not sit a chess knight's move apart.
+pub struct AntiKnight;
+
+impl Constraint for AntiKnight {
+    fn affected_cells(&self, size: usize, pos: usize) -> Vec<usize> {
+        const MOVES: [(i32, i32); 8] = [
+            (1, 2),
+            (2, 1),
+            (-1, 2),
+            (-2, 1),
+            (1, -2),
+            (2, -1),
+            (-1, -2),
+            (-2, -1),
+        ];
+        let row = (pos / size) as i32;
+        let col = (pos % size) as i32;
+        let n = size as i32;
+        let mut cells = Vec::new();
+        for (dr, dc) in MOVES {
+            let (r, c) = (row + dr, col + dc);
+            if r >= 0 && r < n && c >= 0 && c < n {
+                cells.push((r as usize) * size + c as usize);
+            }
+        }
+        cells
+    }
+}
+
+/// A killer cage: a set of cells that must be all-different and sum to `target`.
+pub struct KillerCage {
+    pub cells: Vec<usize>,
+    pub target: i32,
+}
+
+impl Constraint for KillerCage {
+    fn allows(&self, grid: &[i32], _size: usize, pos: usize, value: i32) -> bool {
+        if !self.cells.contains(&pos) {
+            return true;
+        }
+        let mut sum = value;
+        let mut empty = self.cells.len() - 1; // cells still to fill besides pos
+        for &cell in &self.cells {
+            if cell == pos {
+                continue;
+            }
+            let v = grid[cell];
+            if v == value {
+                return false; // no repeats within a cage
+            }
+            if v != 0 {
+                sum += v;
+                empty -= 1;
+            }
+        }
+        if empty == 0 {
+            sum == self.target
+        } else {
+            sum < self.target
+        }
+    }
+
+    fn affected_cells(&self, _size: usize, pos: usize) -> Vec<usize> {
+        if self.cells.contains(&pos) {
+            self.cells.clone()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+fn push_unique(cells: &mut Vec<usize>, cell: usize) {
+    if !cells.contains(&cell) {
+        cells.push(cell);
+    }
+}
@@ -79,6 +79,13 @@ pub struct DifficultyMetrics {
     pub max_clues: usize,
 }
 
+impl DifficultyMetrics {
+    /// Reports whether an achieved clue count lands inside the target band.
+    pub fn clue_count_in_band(&self, clue_count: usize) -> bool {
+        (self.min_clues..=self.max_clues).contains(&clue_count)
+    }
+}
+
 pub fn calculate_metrics(size: usize, difficulty: i32) -> DifficultyMetrics {
     let total_cells = size * size;
     let target_clues = calculate_clue_count(size, difficulty);
@@ -0,0 +1,263 @@
+/// Exact-cover Sudoku solver using Knuth's Dancing Links (Algorithm X).
+///
+/// Sudoku is modelled as a sparse 0/1 matrix with four column groups, each
+/// holding `size*size` columns:
+///
+/// * cell-filled `(r, c)` — every cell must hold exactly one digit,
+/// * row-has-digit `(r, v)` — every digit appears once per row,
+/// * col-has-digit `(c, v)` — every digit appears once per column,
+/// * box-has-digit `(b, v)` — every digit appears once per box.
+///
+/// Each `(r, c, v)` candidate is one matrix row touching exactly those four
+/// columns. Counting exact covers of this matrix counts Sudoku solutions, and
+/// because `cover`/`uncover` are O(column size) the search stays tractable on
+/// the larger grids where naive backtracking blows up.
+
+/// Sentinel used as the circular list root (also index 0 in the node arena).
+const ROOT: usize = 0;
+
+/// Dancing Links matrix stored as parallel arrays (an arena of nodes).
+///
+/// Node `0` is the root header; nodes `1..=num_cols` are the column headers;
+/// the remainder are data nodes, four per candidate row.
+struct DancingLinks {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Column header each data node belongs to.
+    col: Vec<usize>,
+    /// Number of active data nodes in each column (indexed by header node).
+    col_size: Vec<usize>,
+    /// Whether each column header has been covered (used to reject clue clashes).
+    covered: Vec<bool>,
+}
+
+impl DancingLinks {
+    fn new(num_cols: usize) -> Self {
+        let mut dlx = DancingLinks {
+            left: Vec::new(),
+            right: Vec::new(),
+            up: Vec::new(),
+            down: Vec::new(),
+            col: Vec::new(),
+            col_size: Vec::new(),
+            covered: vec![false; num_cols + 1],
+        };
+
+        // Root + one header per column, linked into a circular doubly-linked
+        // list along the top row.
+        for i in 0..=num_cols {
+            dlx.push_node(i);
+            dlx.col[i] = i;
+        }
+        for i in 0..=num_cols {
+            dlx.left[i] = if i == 0 { num_cols } else { i - 1 };
+            dlx.right[i] = if i == num_cols { ROOT } else { i + 1 };
+        }
+
+        dlx
+    }
+
+    /// Appends a fresh node that initially links to itself in every direction.
+    fn push_node(&mut self, self_idx: usize) {
+        self.left.push(self_idx);
+        self.right.push(self_idx);
+        self.up.push(self_idx);
+        self.down.push(self_idx);
+        self.col.push(self_idx);
+        self.col_size.push(0);
+    }
+
+    /// Adds one matrix row given the column headers its 1s sit in.
+    fn add_row(&mut self, columns: &[usize]) {
+        let mut first = None;
+        let mut prev = None;
+
+        for &c in columns {
+            let node = self.left.len();
+            self.push_node(node);
+
+            // Splice into the column's vertical list, above the header.
+            let up = self.up[c];
+            self.up[node] = up;
+            self.down[node] = c;
+            self.down[up] = node;
+            self.up[c] = node;
+            self.col[node] = c;
+            self.col_size[c] += 1;
+
+            // Splice into the row's horizontal list.
+            match prev {
+                None => {
+                    first = Some(node);
+                    self.left[node] = node;
+                    self.right[node] = node;
+                }
+                Some(p) => {
+                    let f = first.unwrap();
+                    self.left[node] = p;
+                    self.right[node] = f;
+                    self.right[p] = node;
+                    self.left[f] = node;
+                }
+            }
+            prev = Some(node);
+        }
+    }
+
+    /// Removes a column header and every row intersecting it from the matrix.
+    fn cover(&mut self, c: usize) {
+        self.covered[c] = true;
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.col_size[self.col[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    /// Restores a column header and its rows (exact inverse of [`cover`]).
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.col_size[self.col[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+        self.covered[c] = false;
+    }
+
+    /// Column with the fewest remaining 1s (Knuth's S heuristic).
+    fn choose_column(&self) -> usize {
+        let mut best = self.right[ROOT];
+        let mut best_size = self.col_size[best];
+        let mut c = self.right[best];
+        while c != ROOT {
+            if self.col_size[c] < best_size {
+                best = c;
+                best_size = self.col_size[c];
+            }
+            c = self.right[c];
+        }
+        best
+    }
+
+    /// Recursively counts exact covers, stopping once `cap` have been found.
+    fn search(&mut self, cap: usize, found: &mut usize) {
+        if self.right[ROOT] == ROOT {
+            *found += 1;
+            return;
+        }
+
+        let c = self.choose_column();
+        self.cover(c);
+
+        let mut r = self.down[c];
+        while r != c {
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.col[j]);
+                j = self.right[j];
+            }
+
+            self.search(cap, found);
+
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.col[j]);
+                j = self.left[j];
+            }
+
+            if *found >= cap {
+                self.uncover(c);
+                return;
+            }
+            r = self.down[r];
+        }
+
+        self.uncover(c);
+    }
+}
+
+/// Counts solutions of `grid` via exact cover, stopping after `cap` are found.
+///
+/// Clue cells are pre-covered before the search so only the unknowns are
+/// solved. A return value of `0` means unsolvable (clashing clues or no
+/// completion),
+/// `1` means a unique solution, and `cap` means "at least `cap`" — callers pass
+/// `cap = 2` to enforce uniqueness cheaply.
+pub fn count_solutions_exact(grid: &[i32], size: usize, cap: usize) -> usize {
+    if cap == 0 {
+        return 0;
+    }
+
+    let sub_grid_size = (size as f64).sqrt() as usize;
+    let boxes_per_row = size / sub_grid_size;
+    let area = size * size;
+    let num_cols = 4 * area;
+
+    // Column header indices are offset by 1 (node 0 is the root).
+    let cell_col = |r: usize, c: usize| 1 + (r * size + c);
+    let row_col = |r: usize, v: usize| 1 + area + (r * size + (v - 1));
+    let col_col = |c: usize, v: usize| 1 + 2 * area + (c * size + (v - 1));
+    let box_col = |b: usize, v: usize| 1 + 3 * area + (b * size + (v - 1));
+
+    let mut dlx = DancingLinks::new(num_cols);
+
+    // One matrix row per (r, c, v) candidate.
+    for r in 0..size {
+        for c in 0..size {
+            let b = (r / sub_grid_size) * boxes_per_row + c / sub_grid_size;
+            for v in 1..=size {
+                dlx.add_row(&[
+                    cell_col(r, c),
+                    row_col(r, v),
+                    col_col(c, v),
+                    box_col(b, v),
+                ]);
+            }
+        }
+    }
+
+    // Pre-cover the columns implied by the given clues.
+    for r in 0..size {
+        for c in 0..size {
+            let v = grid[r * size + c];
+            if v == 0 {
+                continue;
+            }
+            let v = v as usize;
+            let b = (r / sub_grid_size) * boxes_per_row + c / sub_grid_size;
+            let cols = [cell_col(r, c), row_col(r, v), col_col(c, v), box_col(b, v)];
+
+            // A clue whose column is already covered contradicts an earlier
+            // clue, so the grid is unsolvable as stated.
+            if cols.iter().any(|&col| dlx.covered[col]) {
+                return 0;
+            }
+            for col in cols {
+                dlx.cover(col);
+            }
+        }
+    }
+
+    let mut found = 0;
+    dlx.search(cap, &mut found);
+    found
+}
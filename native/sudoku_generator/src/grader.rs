@@ -0,0 +1,491 @@
+/// Human-style difficulty grading.
+///
+/// Clue percentage is a poor proxy for difficulty, so this module grades a
+/// puzzle the way a person would: it keeps a pencil-mark grid of candidate
+/// bitmasks and repeatedly applies a fixed ladder of logical techniques in
+/// increasing cost order, recording the hardest one that had to fire. The
+/// resulting [`DifficultyRating`] drives the difficulty band a puzzle lands in.
+
+/// A single logical solving technique, ordered from cheapest to hardest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    PointingPair,
+    BoxLineReduction,
+}
+
+impl Technique {
+    /// The full ladder, cheapest first. Grading always walks this order.
+    const LADDER: [Technique; 8] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::PointingPair,
+        Technique::BoxLineReduction,
+    ];
+
+    /// Relative cost used to accumulate the puzzle score.
+    pub fn cost(self) -> u32 {
+        match self {
+            Technique::NakedSingle => 1,
+            Technique::HiddenSingle => 2,
+            Technique::NakedPair => 4,
+            Technique::HiddenPair => 6,
+            Technique::NakedTriple => 8,
+            Technique::HiddenTriple => 10,
+            Technique::PointingPair => 12,
+            Technique::BoxLineReduction => 14,
+        }
+    }
+
+    /// Stable display name for the technique.
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "naked_single",
+            Technique::HiddenSingle => "hidden_single",
+            Technique::NakedPair => "naked_pair",
+            Technique::HiddenPair => "hidden_pair",
+            Technique::NakedTriple => "naked_triple",
+            Technique::HiddenTriple => "hidden_triple",
+            Technique::PointingPair => "pointing_pair",
+            Technique::BoxLineReduction => "box_line_reduction",
+        }
+    }
+}
+
+/// The outcome of grading a puzzle with the logical solver.
+pub struct DifficultyRating {
+    /// Whether the ladder solved the puzzle outright.
+    pub solved: bool,
+    /// Hardest technique that was required (`None` if no technique fired).
+    pub hardest_technique: Option<Technique>,
+    /// How often each technique fired, in ladder order.
+    pub technique_counts: Vec<(Technique, usize)>,
+    /// Weighted effort score (sum of `cost * count`).
+    pub score: u32,
+}
+
+impl DifficultyRating {
+    /// Maps the rating onto the 0-3 difficulty bands used by the generator.
+    pub fn difficulty_band(&self) -> i32 {
+        if !self.solved {
+            return 3; // Needs techniques harder than our ladder: expert.
+        }
+        match self.hardest_technique {
+            None | Some(Technique::NakedSingle) | Some(Technique::HiddenSingle) => 0,
+            Some(Technique::NakedPair) | Some(Technique::HiddenPair) => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// Grades a puzzle by logical solving and returns its [`DifficultyRating`].
+pub fn grade(grid: &[i32], size: usize) -> DifficultyRating {
+    let mut solver = PencilGrid::new(grid, size);
+    let mut counts = [0usize; Technique::LADDER.len()];
+
+    // Apply the cheapest technique that makes progress, then restart the ladder.
+    'outer: loop {
+        for (i, &technique) in Technique::LADDER.iter().enumerate() {
+            if solver.apply(technique) {
+                counts[i] += 1;
+                continue 'outer;
+            }
+        }
+        break; // No technique made progress.
+    }
+
+    let technique_counts: Vec<(Technique, usize)> = Technique::LADDER
+        .iter()
+        .zip(counts.iter())
+        .filter(|(_, &n)| n > 0)
+        .map(|(&t, &n)| (t, n))
+        .collect();
+
+    let hardest_technique = technique_counts.iter().map(|&(t, _)| t).max();
+    let score = technique_counts
+        .iter()
+        .map(|&(t, n)| t.cost() * n as u32)
+        .sum();
+
+    DifficultyRating {
+        solved: solver.is_solved(),
+        hardest_technique,
+        technique_counts,
+        score,
+    }
+}
+
+/// A pencil-mark grid: candidate bitmasks plus the units each cell belongs to.
+struct PencilGrid {
+    size: usize,
+    full_mask: u128,
+    /// Candidate mask per cell; a solved cell keeps just its placed bit.
+    cands: Vec<u128>,
+    /// Placed value per cell (0 when unsolved).
+    placed: Vec<i32>,
+    /// Row, column and box membership as lists of cell indices.
+    units: Vec<Vec<usize>>,
+}
+
+impl PencilGrid {
+    fn new(grid: &[i32], size: usize) -> Self {
+        let sub = (size as f64).sqrt() as usize;
+        let boxes_per_row = size / sub;
+        let full_mask = if size >= 128 { u128::MAX } else { (1u128 << size) - 1 };
+
+        let mut units: Vec<Vec<usize>> = Vec::with_capacity(size * 3);
+        for r in 0..size {
+            units.push((0..size).map(|c| r * size + c).collect());
+        }
+        for c in 0..size {
+            units.push((0..size).map(|r| r * size + c).collect());
+        }
+        for b in 0..size {
+            let br = (b / boxes_per_row) * sub;
+            let bc = (b % boxes_per_row) * sub;
+            let mut cells = Vec::with_capacity(size);
+            for dr in 0..sub {
+                for dc in 0..sub {
+                    cells.push((br + dr) * size + (bc + dc));
+                }
+            }
+            units.push(cells);
+        }
+
+        let mut pg = PencilGrid {
+            size,
+            full_mask,
+            cands: vec![full_mask; size * size],
+            placed: vec![0; size * size],
+            units,
+        };
+
+        for pos in 0..size * size {
+            let v = grid[pos];
+            if v != 0 {
+                pg.place(pos, v);
+            }
+        }
+        pg
+    }
+
+    fn is_solved(&self) -> bool {
+        self.placed.iter().all(|&v| v != 0)
+    }
+
+    /// Places `value` at `pos` and eliminates it from every peer cell.
+    fn place(&mut self, pos: usize, value: i32) {
+        let bit = 1u128 << (value - 1);
+        self.placed[pos] = value;
+        self.cands[pos] = bit;
+        let peers: Vec<usize> = self.peers(pos);
+        for peer in peers {
+            self.cands[peer] &= !bit;
+        }
+    }
+
+    /// All cells sharing a unit with `pos` (excluding `pos` itself).
+    fn peers(&self, pos: usize) -> Vec<usize> {
+        let mut peers = Vec::new();
+        for unit in &self.units {
+            if unit.contains(&pos) {
+                for &cell in unit {
+                    if cell != pos && !peers.contains(&cell) {
+                        peers.push(cell);
+                    }
+                }
+            }
+        }
+        peers
+    }
+
+    /// Dispatches to the implementation of one technique.
+    fn apply(&mut self, technique: Technique) -> bool {
+        match technique {
+            Technique::NakedSingle => self.naked_single(),
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::NakedPair => self.naked_subset(2),
+            Technique::HiddenPair => self.hidden_subset(2),
+            Technique::NakedTriple => self.naked_subset(3),
+            Technique::HiddenTriple => self.hidden_subset(3),
+            Technique::PointingPair => self.pointing(),
+            Technique::BoxLineReduction => self.box_line_reduction(),
+        }
+    }
+
+    fn naked_single(&mut self) -> bool {
+        for pos in 0..self.size * self.size {
+            if self.placed[pos] == 0 && self.cands[pos].count_ones() == 1 {
+                let value = self.cands[pos].trailing_zeros() as i32 + 1;
+                self.place(pos, value);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn hidden_single(&mut self) -> bool {
+        for u in 0..self.units.len() {
+            for v in 1..=self.size {
+                let bit = 1u128 << (v - 1);
+                let mut spot = None;
+                let mut count = 0;
+                for &cell in &self.units[u] {
+                    if self.placed[cell] == 0 && self.cands[cell] & bit != 0 {
+                        spot = Some(cell);
+                        count += 1;
+                        if count > 1 {
+                            break;
+                        }
+                    }
+                }
+                if count == 1 {
+                    self.place(spot.unwrap(), v as i32);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Naked pair/triple: `n` unsolved cells in a unit whose candidate union has
+    /// exactly `n` bits let us strip those digits from the rest of the unit.
+    fn naked_subset(&mut self, n: u32) -> bool {
+        for u in 0..self.units.len() {
+            let open: Vec<usize> = self.units[u]
+                .iter()
+                .copied()
+                .filter(|&c| self.placed[c] == 0)
+                .collect();
+
+            let combos = combinations(&open, n as usize);
+            for combo in combos {
+                let mut union = 0u128;
+                for &cell in &combo {
+                    union |= self.cands[cell];
+                }
+                if union.count_ones() != n {
+                    continue;
+                }
+                let mut changed = false;
+                for &cell in &open {
+                    if combo.contains(&cell) {
+                        continue;
+                    }
+                    let before = self.cands[cell];
+                    self.cands[cell] &= !union;
+                    if self.cands[cell] != before {
+                        changed = true;
+                    }
+                }
+                if changed {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Hidden pair/triple: `n` digits confined to the same `n` cells in a unit
+    /// let us remove every other candidate from those cells.
+    fn hidden_subset(&mut self, n: u32) -> bool {
+        for u in 0..self.units.len() {
+            let digits: Vec<usize> = (1..=self.size)
+                .filter(|&v| {
+                    let bit = 1u128 << (v - 1);
+                    self.units[u]
+                        .iter()
+                        .any(|&c| self.placed[c] == 0 && self.cands[c] & bit != 0)
+                })
+                .collect();
+
+            for combo in combinations(&digits, n as usize) {
+                let mut digit_mask = 0u128;
+                for &v in &combo {
+                    digit_mask |= 1u128 << (v - 1);
+                }
+                let cells: Vec<usize> = self.units[u]
+                    .iter()
+                    .copied()
+                    .filter(|&c| self.placed[c] == 0 && self.cands[c] & digit_mask != 0)
+                    .collect();
+
+                if cells.len() != n as usize {
+                    continue;
+                }
+                let mut changed = false;
+                for &cell in &cells {
+                    let before = self.cands[cell];
+                    self.cands[cell] &= digit_mask;
+                    if self.cands[cell] != before {
+                        changed = true;
+                    }
+                }
+                if changed {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Pointing pair/triple: a digit confined to one line within a box is
+    /// eliminated from the rest of that line.
+    fn pointing(&mut self) -> bool {
+        let size = self.size;
+        let sub = (size as f64).sqrt() as usize;
+        let boxes_per_row = size / sub;
+
+        for b in 0..size {
+            let br = (b / boxes_per_row) * sub;
+            let bc = (b % boxes_per_row) * sub;
+            for v in 1..=size {
+                let bit = 1u128 << (v - 1);
+                let mut rows = Vec::new();
+                let mut cols = Vec::new();
+                for dr in 0..sub {
+                    for dc in 0..sub {
+                        let r = br + dr;
+                        let c = bc + dc;
+                        let pos = r * size + c;
+                        if self.placed[pos] == 0 && self.cands[pos] & bit != 0 {
+                            if !rows.contains(&r) {
+                                rows.push(r);
+                            }
+                            if !cols.contains(&c) {
+                                cols.push(c);
+                            }
+                        }
+                    }
+                }
+                if rows.len() == 1 && self.eliminate_line_outside_box(bit, true, rows[0], bc, sub) {
+                    return true;
+                }
+                if cols.len() == 1 && self.eliminate_line_outside_box(bit, false, cols[0], br, sub) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Box/line reduction: a digit confined to one box within a line is
+    /// eliminated from the rest of that box.
+    fn box_line_reduction(&mut self) -> bool {
+        let size = self.size;
+        let sub = (size as f64).sqrt() as usize;
+
+        for line in 0..size {
+            for &is_row in &[true, false] {
+                for v in 1..=size {
+                    let bit = 1u128 << (v - 1);
+                    let mut boxes = Vec::new();
+                    for k in 0..size {
+                        let pos = if is_row { line * size + k } else { k * size + line };
+                        if self.placed[pos] == 0 && self.cands[pos] & bit != 0 {
+                            let (r, c) = (pos / size, pos % size);
+                            let box_idx = (r / sub) * (size / sub) + c / sub;
+                            if !boxes.contains(&box_idx) {
+                                boxes.push(box_idx);
+                            }
+                        }
+                    }
+                    if boxes.len() == 1 && self.eliminate_box_outside_line(bit, boxes[0], is_row, line, sub) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Strips `bit` from cells on `line` that fall outside the box at `box_start`.
+    fn eliminate_line_outside_box(
+        &mut self,
+        bit: u128,
+        is_row: bool,
+        line: usize,
+        box_start: usize,
+        sub: usize,
+    ) -> bool {
+        let size = self.size;
+        let mut changed = false;
+        for k in 0..size {
+            if k >= box_start && k < box_start + sub {
+                continue;
+            }
+            let pos = if is_row { line * size + k } else { k * size + line };
+            if self.placed[pos] == 0 && self.cands[pos] & bit != 0 {
+                self.cands[pos] &= !bit;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Strips `bit` from cells in `box_idx` that fall outside `line`.
+    fn eliminate_box_outside_line(
+        &mut self,
+        bit: u128,
+        box_idx: usize,
+        line_is_row: bool,
+        line: usize,
+        sub: usize,
+    ) -> bool {
+        let size = self.size;
+        let boxes_per_row = size / sub;
+        let br = (box_idx / boxes_per_row) * sub;
+        let bc = (box_idx % boxes_per_row) * sub;
+        let mut changed = false;
+        for dr in 0..sub {
+            for dc in 0..sub {
+                let r = br + dr;
+                let c = bc + dc;
+                if (line_is_row && r == line) || (!line_is_row && c == line) {
+                    continue;
+                }
+                let pos = r * size + c;
+                if self.placed[pos] == 0 && self.cands[pos] & bit != 0 {
+                    self.cands[pos] &= !bit;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// All `n`-element combinations of `items`, preserving input order.
+fn combinations(items: &[usize], n: usize) -> Vec<Vec<usize>> {
+    let mut result = Vec::new();
+    if n == 0 || n > items.len() {
+        return result;
+    }
+    let mut idx: Vec<usize> = (0..n).collect();
+    loop {
+        result.push(idx.iter().map(|&i| items[i]).collect());
+        let mut i = n;
+        loop {
+            if i == 0 {
+                return result;
+            }
+            i -= 1;
+            if idx[i] != i + items.len() - n {
+                break;
+            }
+        }
+        idx[i] += 1;
+        for j in i + 1..n {
+            idx[j] = idx[j - 1] + 1;
+        }
+    }
+}
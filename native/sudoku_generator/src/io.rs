@@ -0,0 +1,154 @@
+/// Parsing and serialization for the common Sudoku interchange formats.
+///
+/// Two formats round-trip through here:
+///
+/// * **flat** — a `size*size`-character string where `.`/`0` is empty and
+///   `1..9` plus letters (`A`=10, `B`=11, ... `Z`=35) are clues; values above
+///   35 have no flat encoding, so only grids up to 25x25 round-trip through it
+///   (`serialize_flat` does not check this and emits non-letter characters), and
+/// * **records** — a `rows,cols` header followed by `row,column,value` lines
+///   using 0-based coordinates and `value == 0` for empty.
+
+/// Which interchange format a grid is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Flat,
+    Records,
+}
+
+impl Format {
+    /// Maps the integer selector passed from Elixir to a format.
+    pub fn from_code(code: i32) -> Option<Format> {
+        match code {
+            0 => Some(Format::Flat),
+            1 => Some(Format::Records),
+            _ => None,
+        }
+    }
+}
+
+/// Parses either supported format, auto-detecting which one was supplied.
+///
+/// Returns the board size and the flattened grid.
+pub fn parse(input: &str) -> Result<(usize, Vec<i32>), String> {
+    match detect_format(input) {
+        Format::Records => parse_records(input),
+        Format::Flat => parse_flat(input),
+    }
+}
+
+/// Heuristically picks the format: a leading `rows,cols` header means records.
+fn detect_format(input: &str) -> Format {
+    if let Some(line) = input.lines().map(str::trim).find(|l| !l.is_empty()) {
+        let parts: Vec<&str> = line.split(',').collect();
+        if parts.len() == 2 && parts.iter().all(|p| p.trim().parse::<usize>().is_ok()) {
+            return Format::Records;
+        }
+    }
+    Format::Flat
+}
+
+/// Parses the flat string format, inferring the size from its length.
+pub fn parse_flat(input: &str) -> Result<(usize, Vec<i32>), String> {
+    let tokens: Vec<char> = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let size = (tokens.len() as f64).sqrt() as usize;
+    if size * size != tokens.len() {
+        return Err(format!(
+            "Flat grid length {} is not a perfect square",
+            tokens.len()
+        ));
+    }
+
+    let mut grid = Vec::with_capacity(tokens.len());
+    for ch in tokens {
+        let value = char_to_value(ch)
+            .ok_or_else(|| format!("Invalid character in flat grid: {:?}", ch))?;
+        if value > size as i32 {
+            return Err(format!("Value {} out of range for size {}", value, size));
+        }
+        grid.push(value);
+    }
+    Ok((size, grid))
+}
+
+/// Parses the `rows,cols` + `row,column,value` record format.
+pub fn parse_records(input: &str) -> Result<(usize, Vec<i32>), String> {
+    let mut lines = input.lines().map(str::trim).filter(|l| !l.is_empty());
+    let header = lines.next().ok_or("Missing rows,cols header")?;
+    let (rows, cols) = parse_pair(header)?;
+    if rows != cols {
+        return Err(format!("Only square grids are supported (got {}x{})", rows, cols));
+    }
+    let size = rows;
+    let mut grid = vec![0i32; size * size];
+
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 3 {
+            return Err(format!("Expected row,column,value but got: {}", line));
+        }
+        let r: usize = fields[0].trim().parse().map_err(|_| "Invalid row")?;
+        let c: usize = fields[1].trim().parse().map_err(|_| "Invalid column")?;
+        let v: i32 = fields[2].trim().parse().map_err(|_| "Invalid value")?;
+        if r >= size || c >= size {
+            return Err(format!("Coordinate ({},{}) out of range for size {}", r, c, size));
+        }
+        if v < 0 || v > size as i32 {
+            return Err(format!("Value {} out of range for size {}", v, size));
+        }
+        grid[r * size + c] = v;
+    }
+    Ok((size, grid))
+}
+
+/// Serializes a grid to the requested interchange format.
+pub fn serialize(grid: &[i32], size: usize, format: Format) -> String {
+    match format {
+        Format::Flat => serialize_flat(grid, size),
+        Format::Records => serialize_records(grid, size),
+    }
+}
+
+/// Serializes a grid to the flat string format.
+pub fn serialize_flat(grid: &[i32], _size: usize) -> String {
+    grid.iter().map(|&v| value_to_char(v)).collect()
+}
+
+/// Serializes a grid to the `rows,cols` + `row,column,value` record format.
+pub fn serialize_records(grid: &[i32], size: usize) -> String {
+    let mut out = format!("{},{}\n", size, size);
+    for (idx, &v) in grid.iter().enumerate() {
+        out.push_str(&format!("{},{},{}\n", idx / size, idx % size, v));
+    }
+    out
+}
+
+fn parse_pair(line: &str) -> Result<(usize, usize), String> {
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() != 2 {
+        return Err(format!("Expected rows,cols but got: {}", line));
+    }
+    let rows = parts[0].trim().parse().map_err(|_| "Invalid rows")?;
+    let cols = parts[1].trim().parse().map_err(|_| "Invalid cols")?;
+    Ok((rows, cols))
+}
+
+/// Decodes one clue character to a value (`0` meaning empty), or `None`.
+fn char_to_value(ch: char) -> Option<i32> {
+    match ch {
+        '.' | '0' => Some(0),
+        '1'..='9' => Some(ch as i32 - '0' as i32),
+        'A'..='Z' => Some(ch as i32 - 'A' as i32 + 10),
+        'a'..='z' => Some(ch as i32 - 'a' as i32 + 10),
+        _ => None,
+    }
+}
+
+/// Encodes a value back to its clue character (`0` becoming `.`).
+fn value_to_char(value: i32) -> char {
+    match value {
+        0 => '.',
+        1..=9 => (b'0' + value as u8) as char,
+        _ => (b'A' + (value - 10) as u8) as char,
+    }
+}
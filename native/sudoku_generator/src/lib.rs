@@ -1,5 +1,9 @@
+mod constraints;
 mod difficulty;
+mod exact_cover;
 mod generator;
+mod grader;
+mod io;
 mod solver;
 
 #[derive(Debug, rustler::NifStruct)]
@@ -11,8 +15,22 @@ pub struct PuzzleResult {
 
 /// Main NIF function to generate a Sudoku puzzle
 /// Uses DirtyCpu scheduler for computationally intensive work
+///
+/// `variant` selects the rule set: 0 classic, 1 X-Sudoku, 2 windoku,
+/// 3 anti-knight. Classic uses the fast generator; variants fall back to
+/// constraint-aware backtracking so the solution is valid under the variant.
+///
+/// `strategy` selects the dig (0 standard clue target, 1 minimal clue set) and
+/// `symmetric` digs cells in rotational 180° pairs for a balanced board.
 #[rustler::nif(schedule = "DirtyCpu", name = "generate_nif")]
-pub fn generate(size: i32, difficulty: i32, seed: u64) -> Result<PuzzleResult, String> {
+pub fn generate(
+    size: i32,
+    difficulty: i32,
+    seed: u64,
+    variant: i32,
+    strategy: i32,
+    symmetric: bool,
+) -> Result<PuzzleResult, String> {
     // Validate inputs
     if !is_valid_size(size) {
         return Err(format!("Invalid size: {}. Must be one of: 9, 16, 25, 36, 49, 100", size));
@@ -22,10 +40,31 @@ pub fn generate(size: i32, difficulty: i32, seed: u64) -> Result<PuzzleResult, S
         return Err(format!("Invalid difficulty: {}. Must be 0-3 (easy, medium, hard, expert)", difficulty));
     }
 
+    let variant = constraints::Variant::from_code(variant)
+        .ok_or_else(|| format!("Invalid variant: {}. Must be 0-3", variant))?;
+    let strategy = generator::RemovalStrategy::from_code(strategy)
+        .ok_or_else(|| format!("Invalid strategy: {}. Must be 0 (standard) or 1 (minimal)", strategy))?;
+
     let size_usize = size as usize;
 
-    // Generate complete solution
-    let solution = generator::generate_solution(size_usize, seed);
+    // Generate complete solution under the active constraint set.
+    let solution = if variant == constraints::Variant::Classic {
+        generator::generate_solution(size_usize, seed)
+    } else {
+        let set = constraints::constraints_for(variant, size_usize);
+        let sol = generator::generate_solution_with_constraints(size_usize, seed, &set)
+            .ok_or_else(|| "Failed to generate a valid variant solution".to_string())?;
+        // Validate against the full variant constraint set.
+        if !solver::is_valid_solution_with(&sol, size_usize, &set) {
+            return Err("Generated invalid variant solution".to_string());
+        }
+        return Ok(PuzzleResult {
+            grid: generator::create_puzzle_with(
+                sol.clone(), difficulty, size_usize, seed + 1, strategy, symmetric,
+            ),
+            solution: sol,
+        });
+    };
 
     // Validate solution
     if !solver::is_valid_solution(&solution, size_usize) {
@@ -33,11 +72,64 @@ pub fn generate(size: i32, difficulty: i32, seed: u64) -> Result<PuzzleResult, S
     }
 
     // Create puzzle by removing cells
-    let grid = generator::create_puzzle(solution.clone(), difficulty, size_usize, seed + 1);
+    let grid = generator::create_puzzle_with(
+        solution.clone(), difficulty, size_usize, seed + 1, strategy, symmetric,
+    );
 
     Ok(PuzzleResult { grid, solution })
 }
 
+/// Result of solving / inspecting an imported puzzle.
+#[derive(Debug, rustler::NifStruct)]
+#[module = "SudokuVersus.Puzzles.SolveResult"]
+pub struct SolveResult {
+    pub grid: Vec<i32>,
+    pub solvable: bool,
+    /// "0", "1" or "multiple" — the uniqueness-capped solution count.
+    pub solution_count: String,
+}
+
+/// NIF to import and solve an externally supplied grid.
+///
+/// Accepts either the flat string or the `row,column,value` record format
+/// (auto-detected) and returns the solved grid plus its solution count.
+#[rustler::nif(schedule = "DirtyCpu", name = "solve_nif")]
+pub fn solve(input: String) -> Result<SolveResult, String> {
+    let (size, grid) = io::parse(&input)?;
+    if !is_valid_size(size as i32) {
+        return Err(format!("Invalid size: {}. Must be one of: 9, 16, 25, 36, 49, 100", size));
+    }
+
+    let solution_count = match exact_cover::count_solutions_exact(&grid, size, 2) {
+        0 => "0",
+        1 => "1",
+        _ => "multiple",
+    };
+
+    let (solvable, solved_grid) = match generator::solve(&grid, size) {
+        Some(solution) => (true, solution),
+        None => (false, grid),
+    };
+
+    Ok(SolveResult {
+        grid: solved_grid,
+        solvable,
+        solution_count: solution_count.to_string(),
+    })
+}
+
+/// NIF to export a grid to one of the interchange formats (0 flat, 1 records).
+#[rustler::nif(name = "export_nif")]
+pub fn export(grid: Vec<i32>, format: i32) -> Result<String, String> {
+    let size = (grid.len() as f64).sqrt() as usize;
+    if size * size != grid.len() {
+        return Err("Grid length is not a perfect square".to_string());
+    }
+    let format = io::Format::from_code(format)
+        .ok_or_else(|| format!("Invalid format: {}. Must be 0 (flat) or 1 (records)", format))?;
+    Ok(io::serialize(&grid, size, format))
+}
+
 fn is_valid_size(size: i32) -> bool {
     matches!(size, 9 | 16 | 25 | 36 | 49 | 100)
 }
@@ -48,8 +140,15 @@ rustler::init!("Elixir.SudokuVersus.Puzzles.Generator");
 mod tests {
     use super::*;
     use crate::difficulty::{calculate_clue_count, calculate_metrics, is_valid_difficulty, difficulty_name};
-    use crate::generator::{generate_solution, create_puzzle, count_solutions};
+    use crate::generator::{generate_solution, create_puzzle, create_puzzle_with, count_solutions, RemovalStrategy};
     use crate::solver::{is_valid_solution, check_constraints};
+    use crate::exact_cover::count_solutions_exact;
+    use crate::grader::{grade, Technique};
+    use crate::constraints::{constraints_for, Variant};
+    use crate::generator::generate_solution_with_constraints;
+    use crate::solver::is_valid_solution_with;
+    use crate::io::{parse, serialize, serialize_flat, Format};
+    use crate::generator::solve as solve_grid;
 
     // ============================================================================
     // NIF Interface Tests (testing logic, NIF tested via Elixir)
@@ -206,4 +305,170 @@ mod tests {
         let grid = vec![1,2,3,4, 3,4,0,2, 2,1,4,3, 4,3,2,1];
         assert!(!check_constraints(&grid, 4, 1, 2, 3)); // 3 already in row
     }
+
+    // ============================================================================
+    // Exact-Cover Solver Tests
+    // ============================================================================
+
+    #[test]
+    fn test_count_solutions_exact_complete() {
+        let sol = generate_solution(9, 12345);
+        assert_eq!(count_solutions_exact(&sol, 9, 2), 1);
+    }
+
+    #[test]
+    fn test_count_solutions_exact_empty_is_multiple() {
+        // An empty grid has many solutions; the cap bounds the count.
+        assert_eq!(count_solutions_exact(&vec![0; 16], 4, 2), 2);
+    }
+
+    #[test]
+    fn test_count_solutions_exact_contradiction() {
+        // Two equal clues in the same row cannot be completed.
+        let mut grid = vec![0; 16];
+        grid[0] = 1;
+        grid[1] = 1;
+        assert_eq!(count_solutions_exact(&grid, 4, 2), 0);
+    }
+
+    // ============================================================================
+    // Difficulty Grading Tests
+    // ============================================================================
+
+    #[test]
+    fn test_grade_solved_grid_is_trivial() {
+        // A complete grid needs no technique and sits in the easy band.
+        let sol = generate_solution(9, 12345);
+        let rating = grade(&sol, 9);
+        assert!(rating.solved);
+        assert_eq!(rating.hardest_technique, None);
+        assert_eq!(rating.difficulty_band(), 0);
+    }
+
+    #[test]
+    fn test_grade_single_removal_uses_naked_single() {
+        // Removing one cell leaves a puzzle solvable by a single naked single.
+        let sol = generate_solution(9, 12345);
+        let mut puzzle = sol.clone();
+        puzzle[40] = 0;
+        let rating = grade(&puzzle, 9);
+        assert!(rating.solved);
+        assert_eq!(rating.hardest_technique, Some(Technique::NakedSingle));
+    }
+
+    #[test]
+    fn test_technique_cost_ordering() {
+        assert!(Technique::NakedSingle.cost() < Technique::HiddenSingle.cost());
+        assert!(Technique::HiddenSingle.cost() < Technique::PointingPair.cost());
+    }
+
+    // ============================================================================
+    // Variant Constraint Tests
+    // ============================================================================
+
+    #[test]
+    fn test_x_sudoku_solution_respects_diagonals() {
+        let set = constraints_for(Variant::XSudoku, 9);
+        let sol = generate_solution_with_constraints(9, 24680, &set).expect("solvable");
+        assert!(is_valid_solution_with(&sol, 9, &set));
+
+        // Both main diagonals must be all-different.
+        let main: std::collections::HashSet<i32> = (0..9).map(|i| sol[i * 9 + i]).collect();
+        let anti: std::collections::HashSet<i32> = (0..9).map(|i| sol[i * 9 + (8 - i)]).collect();
+        assert_eq!(main.len(), 9);
+        assert_eq!(anti.len(), 9);
+    }
+
+    #[test]
+    fn test_anti_knight_solution_is_base_valid() {
+        let set = constraints_for(Variant::AntiKnight, 9);
+        let sol = generate_solution_with_constraints(9, 13579, &set).expect("solvable");
+        assert!(is_valid_solution_with(&sol, 9, &set));
+        assert!(is_valid_solution(&sol, 9));
+    }
+
+    #[test]
+    fn test_variant_from_code() {
+        assert_eq!(Variant::from_code(1), Some(Variant::XSudoku));
+        assert_eq!(Variant::from_code(9), None);
+    }
+
+    // ============================================================================
+    // Import / Export Tests
+    // ============================================================================
+
+    #[test]
+    fn test_parse_flat_4x4() {
+        let (size, grid) = parse("12.4....3......1").unwrap();
+        assert_eq!(size, 4);
+        assert_eq!(grid[0], 1);
+        assert_eq!(grid[2], 0); // '.' is empty
+    }
+
+    #[test]
+    fn test_parse_records_header_and_cells() {
+        let input = "4,4\n0,0,1\n1,2,3\n";
+        let (size, grid) = parse(input).unwrap();
+        assert_eq!(size, 4);
+        assert_eq!(grid[0], 1);
+        assert_eq!(grid[6], 3); // row 1, col 2
+    }
+
+    #[test]
+    fn test_flat_round_trips() {
+        let sol = generate_solution(9, 12345);
+        let flat = serialize_flat(&sol, 9);
+        let (size, grid) = parse(&flat).unwrap();
+        assert_eq!(size, 9);
+        assert_eq!(grid, sol);
+    }
+
+    #[test]
+    fn test_records_round_trips() {
+        let sol = generate_solution(9, 999);
+        let records = serialize(&sol, 9, Format::Records);
+        let (_, grid) = parse(&records).unwrap();
+        assert_eq!(grid, sol);
+    }
+
+    #[test]
+    fn test_solve_completes_puzzle() {
+        let sol = generate_solution(9, 12345);
+        let puzzle = create_puzzle(sol.clone(), 1, 9, 67890);
+        let solved = solve_grid(&puzzle, 9).expect("solvable");
+        assert!(is_valid_solution(&solved, 9));
+    }
+
+    // ============================================================================
+    // Removal Strategy Tests
+    // ============================================================================
+
+    #[test]
+    fn test_symmetric_removal_keeps_symmetry() {
+        let sol = generate_solution(9, 12345);
+        let puzzle = create_puzzle_with(sol, 1, 9, 67890, RemovalStrategy::Standard, true);
+        // Every empty cell must have its 180° reflection empty too.
+        for idx in 0..81 {
+            if puzzle[idx] == 0 {
+                assert_eq!(puzzle[80 - idx], 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimal_removal_digs_at_least_as_deep() {
+        let sol = generate_solution(9, 12345);
+        let standard = create_puzzle_with(sol.clone(), 1, 9, 67890, RemovalStrategy::Standard, false);
+        let minimal = create_puzzle_with(sol, 1, 9, 67890, RemovalStrategy::Minimal, false);
+        let standard_clues = standard.iter().filter(|&&x| x != 0).count();
+        let minimal_clues = minimal.iter().filter(|&&x| x != 0).count();
+        assert!(minimal_clues <= standard_clues);
+    }
+
+    #[test]
+    fn test_clue_count_in_band() {
+        let m = calculate_metrics(9, 1);
+        assert!(m.clue_count_in_band(m.target_clues));
+        assert!(!m.clue_count_in_band(m.max_clues + 1));
+    }
 }